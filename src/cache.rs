@@ -0,0 +1,189 @@
+use std::fs;
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+use crate::folder_selector::{read_directory, FileNode};
+
+/// Write a fully-walked `FileNode` tree to `path` as a zstd-compressed
+/// bincode blob so a later `read_index` of the same root is near-instant.
+/// `root` is stored alongside the tree so a reload can be validated against
+/// the root it was taken from.
+pub fn write_index(root: &Path, node: &FileNode, path: &Path) -> io::Result<()> {
+    let payload = (root.to_string_lossy().into_owned(), node);
+    let encoded =
+        bincode::serialize(&payload).map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+    let compressed =
+        zstd::encode_all(&encoded[..], 0).map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+    let mut f = File::create(path)?;
+    f.write_all(&compressed)
+}
+
+/// Decode the `(root, node)` payload a cache file holds, without discarding
+/// the stored root — callers that need to validate the cache against an
+/// expected root (e.g. `read_directory_cached`) need both.
+fn read_index_raw(path: &Path) -> io::Result<(String, FileNode)> {
+    let mut compressed = Vec::new();
+    File::open(path)?.read_to_end(&mut compressed)?;
+    let encoded =
+        zstd::decode_all(&compressed[..]).map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+    bincode::deserialize(&encoded).map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+}
+
+/// Load a tree previously written by `write_index`.
+pub fn read_index(path: &Path) -> io::Result<FileNode> {
+    read_index_raw(path).map(|(_root, node)| node)
+}
+
+/// Load the cached tree at `index_path` if one exists, is readable, and was
+/// written for this same `root` (a cache written for a different root is
+/// discarded rather than merged), re-walking only the subtrees whose
+/// directory mtime no longer matches the cached `modified` value, then
+/// write the refreshed tree back to the cache. Falls back to a full
+/// `read_directory` walk when there is no usable cache.
+pub fn read_directory_cached<P: AsRef<Path>>(
+    root: P,
+    index_path: &Path,
+    max_depth: Option<u32>,
+) -> io::Result<FileNode> {
+    let root = root.as_ref();
+    let root_str = root.to_string_lossy().into_owned();
+
+    let node = match read_index_raw(index_path) {
+        Ok((cached_root, cached)) if cached_root == root_str => {
+            refresh_stale(root, cached, max_depth, 0)?
+        }
+        _ => read_directory(root, max_depth)?,
+    };
+
+    write_index(root, &node, index_path)?;
+    Ok(node)
+}
+
+/// Compare a cached node's `modified` mtime against the live filesystem; if
+/// it still matches, recurse into its children looking for staleness
+/// further down, otherwise re-walk this directory from scratch. `depth` is
+/// this directory's absolute depth from the original walk root, so a
+/// re-walk is given only the remaining depth budget (`max_depth - depth`)
+/// rather than the full `max_depth`, matching what the original walk would
+/// have produced for this subtree.
+fn refresh_stale(
+    path: &Path,
+    cached: FileNode,
+    max_depth: Option<u32>,
+    depth: u32,
+) -> io::Result<FileNode> {
+    if !cached.is_dir {
+        return Ok(cached);
+    }
+
+    let live_modified = fs::metadata(path).ok().and_then(|m| modified_unix_seconds(&m));
+    if live_modified != cached.modified {
+        // this directory's own contents changed: re-walk it fresh, limited
+        // to the depth budget remaining at this point in the tree
+        let remaining = max_depth.map(|m| m.saturating_sub(depth));
+        return read_directory(path, remaining);
+    }
+
+    if cached.has_more == Some(true) && max_depth.map_or(true, |m| depth < m) {
+        // this directory wasn't stale, but it was previously truncated by a
+        // shallower max_depth and the caller is now asking to go deeper, so
+        // its uncached children need a real walk, not just a staleness check
+        let remaining = max_depth.map(|m| m.saturating_sub(depth));
+        return read_directory(path, remaining);
+    }
+
+    match cached.children {
+        Some(children) => {
+            let mut refreshed = Vec::with_capacity(children.len());
+            for child in children {
+                let child_path = PathBuf::from(&child.path);
+                refreshed.push(refresh_stale(&child_path, child, max_depth, depth + 1)?);
+            }
+            let size = refreshed.iter().map(|c| c.size).sum();
+            Ok(FileNode {
+                children: Some(refreshed),
+                size,
+                ..cached
+            })
+        }
+        None => Ok(cached),
+    }
+}
+
+fn modified_unix_seconds(meta: &fs::Metadata) -> Option<u64> {
+    meta.modified()
+        .ok()?
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()
+        .map(|d| d.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unique_temp_dir(label: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "fwalker-rs-cache-test-{}-{}-{}",
+            label,
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn deeper_request_rewalks_a_previously_truncated_directory() {
+        let root = unique_temp_dir("depth-increase");
+        let nested = root.join("a").join("b");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(nested.join("leaf.txt"), b"hi").unwrap();
+
+        let index_path = root.join("index.bin");
+
+        // first call caps depth at 1, so "a" is reported with has_more and
+        // its contents (including "b") are never loaded
+        let shallow = read_directory_cached(&root, &index_path, Some(1)).unwrap();
+        let a = shallow
+            .children
+            .as_ref()
+            .unwrap()
+            .iter()
+            .find(|c| c.name == "a")
+            .unwrap();
+        assert_eq!(a.has_more, Some(true));
+        assert!(a.children.is_none());
+
+        // nothing on disk changed, only the requested depth increased: the
+        // cache must still re-walk "a" rather than replaying the shallow copy
+        let deeper = read_directory_cached(&root, &index_path, None).unwrap();
+        let a = deeper
+            .children
+            .as_ref()
+            .unwrap()
+            .iter()
+            .find(|c| c.name == "a")
+            .unwrap();
+        assert_eq!(a.has_more, Some(false));
+        let b = a
+            .children
+            .as_ref()
+            .unwrap()
+            .iter()
+            .find(|c| c.name == "b")
+            .unwrap();
+        assert!(b
+            .children
+            .as_ref()
+            .unwrap()
+            .iter()
+            .any(|c| c.name == "leaf.txt"));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,179 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::hash::Hasher;
+use std::io::{self, BufReader, Read};
+use std::path::Path;
+
+use twox_hash::XxHash64;
+
+use crate::folder_formatter::file_tree::FileType as FT;
+use crate::folder_selector::collect_paths;
+
+/// How `find_duplicates` groups files together.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DuplicateMethod {
+    /// Group files that share an identical byte length.
+    Size,
+    /// Within each same-size group, hash contents and group by digest.
+    Hash,
+}
+
+const HASH_BUF_SIZE: usize = 64 * 1024;
+
+fn hash_file(path: &Path) -> io::Result<u64> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut hasher = XxHash64::with_seed(0);
+    let mut buf = [0u8; HASH_BUF_SIZE];
+    loop {
+        let n = reader.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.write(&buf[..n]);
+    }
+    Ok(hasher.finish())
+}
+
+/// Compare two files byte-for-byte. Used to confirm a hash match is a true
+/// duplicate rather than a 64-bit digest collision.
+fn files_equal(a: &Path, b: &Path) -> io::Result<bool> {
+    let mut ra = BufReader::new(File::open(a)?);
+    let mut rb = BufReader::new(File::open(b)?);
+    let mut bufa = [0u8; HASH_BUF_SIZE];
+    let mut bufb = [0u8; HASH_BUF_SIZE];
+    loop {
+        let na = ra.read(&mut bufa)?;
+        let nb = rb.read(&mut bufb)?;
+        if na != nb || bufa[..na] != bufb[..nb] {
+            return Ok(false);
+        }
+        if na == 0 {
+            return Ok(true);
+        }
+    }
+}
+
+/// Split a same-hash bucket into sub-clusters of files that are actually
+/// byte-identical, since a 64-bit hash alone can't guarantee that.
+fn verify_hash_bucket(candidates: Vec<String>) -> Vec<Vec<String>> {
+    let mut clusters: Vec<Vec<String>> = Vec::new();
+    for path in candidates {
+        let mut placed = false;
+        for cluster in clusters.iter_mut() {
+            if files_equal(Path::new(&path), Path::new(&cluster[0])).unwrap_or(false) {
+                cluster.push(path.clone());
+                placed = true;
+                break;
+            }
+        }
+        if !placed {
+            clusters.push(vec![path]);
+        }
+    }
+    clusters.into_iter().filter(|c| c.len() > 1).collect()
+}
+
+/// Find clusters of byte-identical files under `root`.
+///
+/// `Size` groups files that share a byte length only; `Hash` additionally
+/// hashes the contents of each same-size group (only files whose size
+/// already collides are ever hashed), then does a final byte-for-byte
+/// comparison within each hash bucket so a 64-bit digest collision never
+/// gets reported as a duplicate. Clusters are returned largest-group-first.
+pub fn find_duplicates(root: &Path, method: DuplicateMethod) -> Vec<Vec<String>> {
+    let mut by_size: HashMap<u64, Vec<String>> = HashMap::new();
+    for (path, ft, size, _modified) in collect_paths(root, None) {
+        if !matches!(ft, FT::File) {
+            continue;
+        }
+        by_size.entry(size).or_default().push(path);
+    }
+
+    let size_groups: Vec<Vec<String>> = by_size
+        .into_values()
+        .filter(|group| group.len() > 1)
+        .collect();
+
+    let mut clusters: Vec<Vec<String>> = match method {
+        DuplicateMethod::Size => size_groups,
+        DuplicateMethod::Hash => {
+            let mut by_hash: HashMap<u64, Vec<String>> = HashMap::new();
+            for group in size_groups {
+                for path in group {
+                    if let Ok(digest) = hash_file(Path::new(&path)) {
+                        by_hash.entry(digest).or_default().push(path);
+                    }
+                }
+            }
+            by_hash
+                .into_values()
+                .filter(|group| group.len() > 1)
+                .flat_map(verify_hash_bucket)
+                .collect()
+        }
+    };
+
+    clusters.sort_by(|a, b| b.len().cmp(&a.len()));
+    clusters
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unique_temp_dir(label: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "fwalker-rs-duplicates-test-{}-{}-{}",
+            label,
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn verify_hash_bucket_splits_a_collision_into_separate_clusters() {
+        let dir = unique_temp_dir("collision");
+        let a = dir.join("a.bin");
+        let b = dir.join("b.bin");
+        let c = dir.join("c.bin");
+        std::fs::write(&a, b"same contents").unwrap();
+        std::fs::write(&b, b"same contents").unwrap();
+        std::fs::write(&c, b"different!!!!").unwrap();
+
+        // simulate a hash bucket that (incorrectly, as a real collision
+        // would) grouped all three paths together
+        let candidates = vec![
+            a.to_string_lossy().into_owned(),
+            b.to_string_lossy().into_owned(),
+            c.to_string_lossy().into_owned(),
+        ];
+        let clusters = verify_hash_bucket(candidates);
+
+        assert_eq!(clusters.len(), 1);
+        assert_eq!(clusters[0].len(), 2);
+        assert!(clusters[0].contains(&a.to_string_lossy().into_owned()));
+        assert!(clusters[0].contains(&b.to_string_lossy().into_owned()));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn find_duplicates_by_hash_ignores_same_size_different_content() {
+        let dir = unique_temp_dir("by-hash");
+        std::fs::write(dir.join("one.txt"), b"AAAA").unwrap();
+        std::fs::write(dir.join("two.txt"), b"AAAA").unwrap();
+        std::fs::write(dir.join("three.txt"), b"BBBB").unwrap();
+
+        let clusters = find_duplicates(&dir, DuplicateMethod::Hash);
+
+        assert_eq!(clusters.len(), 1);
+        assert_eq!(clusters[0].len(), 2);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,11 +1,15 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
 
 use crate::folder_formatter::file_tree::FileType as FT;
 use crate::folder_formatter::json_formatting::format_paths;
+use crate::symlink::{dir_identity, LinkStatus};
+use crate::walk_options::{read_gitignore_patterns, PatternMatcher, WalkOptions};
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 pub struct FileNode {
     pub name: String,
     pub path: String,
@@ -13,6 +17,32 @@ pub struct FileNode {
     pub children: Option<Vec<FileNode>>,
     // indicates if there are more children not yet loaded (for "lazy load")
     pub has_more: Option<bool>,
+    /// The raw target of a symlink entry, read via `fs::read_link`. `None`
+    /// for non-symlinks.
+    pub link_target: Option<String>,
+    /// Healthy/broken/cyclic status of a symlink entry. `None` for
+    /// non-symlinks.
+    pub link_status: Option<LinkStatus>,
+    /// Last-modified time as unix-epoch seconds, read from the same
+    /// `symlink_metadata` call already made to build this node. Used by the
+    /// on-disk cache to decide whether a directory needs re-walking.
+    pub modified: Option<u64>,
+    /// File size in bytes. For a directory this is the summed size of its
+    /// loaded descendants, so a directory collapsed by `has_more` (lazy
+    /// loading, max depth) reports `0` rather than an unknown total.
+    pub size: u64,
+}
+
+fn children_size(children: &[FileNode]) -> u64 {
+    children.iter().map(|c| c.size).sum()
+}
+
+fn unix_seconds(meta: &fs::Metadata) -> Option<u64> {
+    meta.modified()
+        .ok()?
+        .duration_since(UNIX_EPOCH)
+        .ok()
+        .map(|d| d.as_secs())
 }
 
 fn dir_has_children(p: &PathBuf) -> bool {
@@ -22,17 +52,74 @@ fn dir_has_children(p: &PathBuf) -> bool {
     }
 }
 
-fn build_node(p: &PathBuf, depth: u32, max_depth: Option<u32>) -> Result<FileNode, std::io::Error> {
+/// Build a `/`-separated path relative to `root`, used to evaluate ignore
+/// patterns the same way `.gitignore` does regardless of the root's own
+/// absolute location.
+pub(crate) fn rel_path_str(root: &Path, p: &Path) -> String {
+    p.strip_prefix(root)
+        .unwrap_or(p)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+fn build_node(
+    p: &PathBuf,
+    depth: u32,
+    max_depth: Option<u32>,
+    root: &Path,
+    matcher: &PatternMatcher,
+    opts: &WalkOptions,
+    ancestors: &HashSet<(u64, u64)>,
+) -> Result<FileNode, std::io::Error> {
     let meta = fs::symlink_metadata(p)?;
-    let is_dir = meta.file_type().is_dir();
+    let is_symlink = meta.file_type().is_symlink();
+    let mut is_dir = meta.file_type().is_dir();
     let name = p
         .file_name()
         .and_then(|n| n.to_str())
         .unwrap_or("")
         .to_string();
     let path_str = p.to_string_lossy().to_string();
+    let modified = unix_seconds(&meta);
+
+    // a followed symlink needs its target's identity to extend the
+    // ancestor chain and to detect cycles before recursing into it
+    let mut followed_identity: Option<(u64, u64)> = None;
+    let (link_target, link_status) = if is_symlink {
+        let target = fs::read_link(p).ok().map(|t| t.to_string_lossy().to_string());
+        match fs::metadata(p) {
+            Err(_) => (target, Some(LinkStatus::Broken)),
+            Ok(target_meta) => {
+                if opts.follow_symlinks && target_meta.is_dir() {
+                    match dir_identity(p) {
+                        Some(id) if ancestors.contains(&id) => {
+                            (target, Some(LinkStatus::InfiniteRecursion))
+                        }
+                        id => {
+                            is_dir = true;
+                            followed_identity = id;
+                            (target, Some(LinkStatus::Healthy))
+                        }
+                    }
+                } else {
+                    (target, Some(LinkStatus::Healthy))
+                }
+            }
+        }
+    } else {
+        (None, None)
+    };
 
     if is_dir {
+        // every real directory's own identity joins the ancestor chain as we
+        // descend into it, not just a followed symlink's target — otherwise
+        // a symlink loop back to a plain (non-symlink) ancestor directory
+        // would go undetected
+        let mut child_ancestors = ancestors.clone();
+        if let Some(id) = followed_identity.or_else(|| dir_identity(p)) {
+            child_ancestors.insert(id);
+        }
+
         if let Some(max) = max_depth {
             if depth >= max {
                 // reached max depth: do not recurse, mark has_more (if the directory is not empty)
@@ -43,36 +130,57 @@ fn build_node(p: &PathBuf, depth: u32, max_depth: Option<u32>) -> Result<FileNod
                     is_dir,
                     children: None,
                     has_more: Some(has_more),
+                    link_target,
+                    link_status,
+                    modified,
+                    size: 0,
                 });
             } else {
-                let children = read_children(p, depth + 1, max_depth)?;
+                let children =
+                    read_children(p, depth + 1, max_depth, root, matcher, opts, &child_ancestors)?;
+                let size = children_size(&children);
                 return Ok(FileNode {
                     name,
                     path: path_str,
                     is_dir,
                     children: Some(children),
                     has_more: Some(false),
+                    link_target,
+                    link_status,
+                    modified,
+                    size,
                 });
             }
         } else {
             // unlimited depth
-            let children = read_children(p, depth + 1, max_depth)?;
+            let children =
+                read_children(p, depth + 1, max_depth, root, matcher, opts, &child_ancestors)?;
+            let size = children_size(&children);
             return Ok(FileNode {
                 name,
                 path: path_str,
                 is_dir,
                 children: Some(children),
                 has_more: Some(false),
+                link_target,
+                link_status,
+                modified,
+                size,
             });
         }
     } else {
-        // file or link
+        // file, broken link, or a link we're not following
+        let size = meta.len();
         return Ok(FileNode {
             name,
             path: path_str,
             is_dir,
             children: None,
             has_more: Some(false),
+            link_target,
+            link_status,
+            modified,
+            size,
         });
     }
 }
@@ -81,6 +189,10 @@ fn read_children(
     dir: &PathBuf,
     depth: u32,
     max_depth: Option<u32>,
+    root: &Path,
+    matcher: &PatternMatcher,
+    opts: &WalkOptions,
+    ancestors: &HashSet<(u64, u64)>,
 ) -> Result<Vec<FileNode>, std::io::Error> {
     let mut items = Vec::new();
     let read = match fs::read_dir(dir) {
@@ -91,16 +203,38 @@ fn read_children(
         }
     };
 
+    // layer this directory's own .gitignore on top of the inherited rules,
+    // so a child's patterns can override its parent's
+    let mut matcher = matcher.clone();
+    if opts.respect_gitignore {
+        if let Some(patterns) = read_gitignore_patterns(dir) {
+            matcher.push_patterns(&patterns);
+        }
+    }
+
     for entry_res in read {
         if let Ok(entry) = entry_res {
             let path = entry.path();
-            // Optional: skip hidden files or folder
-            // if let Some(fname) = path.file_name().and_then(|n| n.to_str()) {
-            //     if fname.starts_with('.') {
-            //         continue;
-            //     }
-            // }
-            match build_node(&path, depth, max_depth) {
+
+            if !opts.include_hidden {
+                if let Some(fname) = path.file_name().and_then(|n| n.to_str()) {
+                    if fname.starts_with('.') {
+                        continue;
+                    }
+                }
+            }
+
+            if !matcher.is_empty() {
+                let is_dir = entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false);
+                let rel = rel_path_str(root, &path);
+                if matcher.matches(&rel, is_dir) {
+                    // matched entries are skipped entirely; for a directory
+                    // this prunes the whole subtree since we never read_dir into it
+                    continue;
+                }
+            }
+
+            match build_node(&path, depth, max_depth, root, &matcher, opts, ancestors) {
                 Ok(node) => items.push(node),
                 Err(_) => continue, // 單個項目錯誤跳過
             }
@@ -119,6 +253,17 @@ fn read_children(
 
 
 pub fn read_directory<P: AsRef<Path>>(path: P, max_depth: Option<u32>) -> Result<FileNode, std::io::Error> {
+    read_directory_with_options(path, max_depth, &WalkOptions::default())
+}
+
+/// Like `read_directory`, but applies an opt-in `WalkOptions` filter layer
+/// (explicit ignore patterns, `.gitignore` layering, hidden-file visibility)
+/// while walking.
+pub fn read_directory_with_options<P: AsRef<Path>>(
+    path: P,
+    max_depth: Option<u32>,
+    opts: &WalkOptions,
+) -> Result<FileNode, std::io::Error> {
     let root = PathBuf::from(path.as_ref());
     if !root.exists() {
         return Err(std::io::Error::new(
@@ -127,10 +272,108 @@ pub fn read_directory<P: AsRef<Path>>(path: P, max_depth: Option<u32>) -> Result
         ));
     }
 
-    build_node(&root, 0, max_depth)
+    let matcher = opts.compile();
+    // start with an empty chain: build_node adds the root's own identity as
+    // it descends into it, so a cycle back to the root is still caught, and
+    // a walk root that is itself a followed symlink is not mistaken for one
+    let ancestors: HashSet<(u64, u64)> = HashSet::new();
+    build_node(&root, 0, max_depth, &root, &matcher, opts, &ancestors)
+}
+
+/// Lazily list the direct children of `path`, one at a time, without
+/// recursing into them. Each child's `has_more` flag is computed from
+/// whether it actually has entries, matching the meaning `has_more` already
+/// carries at `max_depth` in the eager `read_directory` walk — this just
+/// makes that level-by-level loading available for every directory.
+pub fn read_children_of<P: AsRef<Path>>(
+    path: P,
+) -> Result<impl Iterator<Item = Result<FileNode, std::io::Error>>, std::io::Error> {
+    read_children_of_with_options(path, &WalkOptions::default())
 }
 
-fn map_file_type(ft: fs::FileType) -> FT {
+/// Like `read_children_of`, but applies the same opt-in `WalkOptions`
+/// filter layer as the eager APIs (ignore patterns, `.gitignore`,
+/// hidden-file visibility) while listing this one level.
+pub fn read_children_of_with_options<P: AsRef<Path>>(
+    path: P,
+    opts: &WalkOptions,
+) -> Result<impl Iterator<Item = Result<FileNode, std::io::Error>>, std::io::Error> {
+    let root = PathBuf::from(path.as_ref());
+    if !root.exists() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("Path not found: {}", root.to_string_lossy()),
+        ));
+    }
+
+    let mut matcher = opts.compile();
+    if opts.respect_gitignore {
+        if let Some(patterns) = read_gitignore_patterns(&root) {
+            matcher.push_patterns(&patterns);
+        }
+    }
+
+    // buffer this one directory's entries so folder-first/name ordering can
+    // be restored before streaming them back out
+    let mut candidates: Vec<(bool, String, PathBuf)> = Vec::new();
+    for entry_res in fs::read_dir(&root)? {
+        let entry = match entry_res {
+            Ok(e) => e,
+            Err(_) => continue,
+        };
+        let child = entry.path();
+
+        if !opts.include_hidden {
+            if let Some(fname) = child.file_name().and_then(|n| n.to_str()) {
+                if fname.starts_with('.') {
+                    continue;
+                }
+            }
+        }
+
+        let is_dir = entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false);
+
+        if !matcher.is_empty() {
+            let rel = rel_path_str(&root, &child);
+            if matcher.matches(&rel, is_dir) {
+                continue;
+            }
+        }
+
+        let name = child
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("")
+            .to_string();
+        candidates.push((is_dir, name, child));
+    }
+
+    candidates.sort_by(|a, b| match (a.0, b.0) {
+        (true, false) => std::cmp::Ordering::Less,
+        (false, true) => std::cmp::Ordering::Greater,
+        _ => a.1.to_lowercase().cmp(&b.1.to_lowercase()),
+    });
+
+    let opts = opts.clone();
+    // seed with the root's own identity so a symlink child pointing back to
+    // this same directory is reported as a cycle rather than a healthy link
+    let ancestors: HashSet<(u64, u64)> = dir_identity(&root).into_iter().collect();
+    Ok(candidates.into_iter().map(move |(_, _, child)| {
+        // depth 0 of max_depth 0: build_node stops immediately without
+        // recursing and derives `has_more` from the directory's own entries
+        build_node(
+            &child,
+            0,
+            Some(0),
+            &root,
+            &PatternMatcher::default(),
+            &opts,
+            &ancestors,
+        )
+    }))
+}
+
+pub(crate) fn map_file_type(ft: fs::FileType) -> FT {
     if ft.is_dir() {
         FT::Directory
     } else if ft.is_symlink() {
@@ -140,36 +383,109 @@ fn map_file_type(ft: fs::FileType) -> FT {
     }
 }
 
-/// 收集 root 下的平坦 (path, FileType) 列表（iterative, 使用 DirEntry.file_type() 儘量避免多次 stat）
-pub fn collect_paths(root: &Path, max_depth: Option<u32>) -> Vec<(String, FT)> {
+/// 收集 root 下的平坦 (path, FileType, size, modified) 列表（iterative, 使用 DirEntry.file_type() 儘量避免多次 stat）
+pub fn collect_paths(root: &Path, max_depth: Option<u32>) -> Vec<(String, FT, u64, Option<u64>)> {
+    collect_paths_with_options(root, max_depth, &WalkOptions::default())
+}
+
+/// Like `collect_paths`, but applies an opt-in `WalkOptions` filter layer
+/// (explicit ignore patterns, `.gitignore` layering, hidden-file visibility)
+/// while walking; a matched directory is never pushed onto the stack, so
+/// its whole subtree is pruned instead of being walked and discarded.
+pub fn collect_paths_with_options(
+    root: &Path,
+    max_depth: Option<u32>,
+    opts: &WalkOptions,
+) -> Vec<(String, FT, u64, Option<u64>)> {
     let mut out = Vec::new();
-    let mut stack: Vec<(PathBuf, u32)> = Vec::new();
-    stack.push((root.to_path_buf(), 0));
+    let mut stack: Vec<(PathBuf, u32, PatternMatcher, HashSet<(u64, u64)>)> = Vec::new();
+    // seed with the root's own identity so a symlink cycle that loops back
+    // to the walk root (not just to some inner ancestor) is also caught
+    let root_ancestors: HashSet<(u64, u64)> = dir_identity(root).into_iter().collect();
+    stack.push((root.to_path_buf(), 0, opts.compile(), root_ancestors));
 
-    while let Some((dir, depth)) = stack.pop() {
+    while let Some((dir, depth, matcher, ancestors)) = stack.pop() {
         let rd = match fs::read_dir(&dir) {
             Ok(rd) => rd,
             Err(_) => continue,
         };
+
+        let mut matcher = matcher;
+        if opts.respect_gitignore {
+            if let Some(patterns) = read_gitignore_patterns(&dir) {
+                matcher.push_patterns(&patterns);
+            }
+        }
+
         for entry_res in rd {
             if let Ok(entry) = entry_res {
                 let path = entry.path();
+
+                if !opts.include_hidden {
+                    if let Some(fname) = path.file_name().and_then(|n| n.to_str()) {
+                        if fname.starts_with('.') {
+                            continue;
+                        }
+                    }
+                }
+
                 // 優先用 DirEntry.file_type()，若失敗再 fallback
                 let ft = entry
                     .file_type()
                     .or_else(|_| fs::symlink_metadata(&path).map(|m| m.file_type()))
                     .ok();
 
+                if !matcher.is_empty() {
+                    let rel = rel_path_str(root, &path);
+                    let is_dir = ft.map(|ft| ft.is_dir()).unwrap_or(false);
+                    if matcher.matches(&rel, is_dir) {
+                        continue;
+                    }
+                }
+
                 if let Some(ft) = ft {
-                    let mapped = map_file_type(ft);
+                    let mut mapped = map_file_type(ft);
+                    let mut followed_identity: Option<(u64, u64)> = None;
+
+                    // a followed symlink-to-directory is walked like a real
+                    // directory, guarded by the same ancestor-chain cycle check
+                    if ft.is_symlink() && opts.follow_symlinks {
+                        if let Ok(target_meta) = fs::metadata(&path) {
+                            if target_meta.is_dir() {
+                                match dir_identity(&path) {
+                                    Some(id) if ancestors.contains(&id) => {
+                                        // cycle: leave classified as a link, don't recurse
+                                    }
+                                    id => {
+                                        mapped = FT::Directory;
+                                        followed_identity = id;
+                                    }
+                                }
+                            }
+                        }
+                    }
+
+                    // reuse the DirEntry's own metadata for size/mtime instead of
+                    // a second stat through the path
+                    let meta = entry.metadata().or_else(|_| fs::symlink_metadata(&path)).ok();
+                    let size = meta.as_ref().map(|m| m.len()).unwrap_or(0);
+                    let modified = meta.as_ref().and_then(unix_seconds);
+
                     // store path as string (relative or absolute as you prefer)
                     let path_str = path.to_string_lossy().into_owned();
-                    out.push((path_str.clone(), mapped.clone()));
+                    out.push((path_str.clone(), mapped.clone(), size, modified));
 
                     // 若為目錄且未達 max_depth，push 到 stack 以繼續掃描
                     if matches!(mapped, FT::Directory) {
                         if max_depth.map_or(true, |m| depth + 1 <= m) {
-                            stack.push((path, depth + 1));
+                            let mut child_ancestors = ancestors.clone();
+                            // every real directory's own identity joins the
+                            // ancestor chain as we descend into it, not just
+                            // a followed symlink's target
+                            if let Some(id) = followed_identity.or_else(|| dir_identity(&path)) {
+                                child_ancestors.insert(id);
+                            }
+                            stack.push((path, depth + 1, matcher.clone(), child_ancestors));
                         }
                     }
                 }
@@ -182,6 +498,17 @@ pub fn collect_paths(root: &Path, max_depth: Option<u32>) -> Vec<(String, FT)> {
 
 
 pub fn read_directory_fast<P: AsRef<Path>>(path: P, max_depth: Option<u32>) -> Result<String, std::io::Error> {
+    read_directory_fast_with_options(path, max_depth, &WalkOptions::default())
+}
+
+/// Like `read_directory_fast`, but applies an opt-in `WalkOptions` filter
+/// layer (explicit ignore patterns, `.gitignore` layering, hidden-file
+/// visibility) while walking.
+pub fn read_directory_fast_with_options<P: AsRef<Path>>(
+    path: P,
+    max_depth: Option<u32>,
+    opts: &WalkOptions,
+) -> Result<String, std::io::Error> {
     let root = path.as_ref();
     if !root.exists() {
         return Err(std::io::Error::new(
@@ -190,7 +517,92 @@ pub fn read_directory_fast<P: AsRef<Path>>(path: P, max_depth: Option<u32>) -> R
         ));
     }
 
-    let children = collect_paths(root, max_depth);
+    let children = collect_paths_with_options(root, max_depth, opts);
+    // format_paths only needs (path, type) pairs; size/modified are for callers
+    // like find_duplicates that want them without a second walk
+    let for_format: Vec<(String, FT)> = children
+        .into_iter()
+        .map(|(path, ft, _size, _modified)| (path, ft))
+        .collect();
     // format_paths 會用 FileTree::new 去建樹並 serialize
-    Ok(format_paths(&root.to_string_lossy(), children))
+    Ok(format_paths(&root.to_string_lossy(), for_format))
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn unique_temp_dir(label: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "fwalker-rs-selector-test-{}-{}-{}",
+            label,
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn collect_paths_finds_nested_files_and_carries_their_size() {
+        let root = unique_temp_dir("collect");
+        fs::create_dir_all(root.join("a")).unwrap();
+        fs::write(root.join("a").join("leaf.txt"), b"hello").unwrap();
+
+        let paths = collect_paths(&root, None);
+        let leaf = paths
+            .iter()
+            .find(|(p, ..)| p.ends_with("leaf.txt"))
+            .unwrap();
+        assert_eq!(leaf.2, 5);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn a_symlink_cycle_back_to_the_root_is_not_followed_infinitely() {
+        let root = unique_temp_dir("cycle");
+        let link = root.join("loop");
+        std::os::unix::fs::symlink(&root, &link).unwrap();
+
+        let opts = WalkOptions {
+            follow_symlinks: true,
+            ..WalkOptions::default()
+        };
+        // the root's own identity is on the ancestor chain when its children
+        // are read, so `loop` is reported as a cycle instead of being followed
+        let node = read_directory_with_options(&root, Some(4), &opts).unwrap();
+        let loop_child = node
+            .children
+            .as_ref()
+            .unwrap()
+            .iter()
+            .find(|c| c.name == "loop")
+            .unwrap();
+        assert_eq!(loop_child.link_status, Some(LinkStatus::InfiniteRecursion));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn read_children_of_with_options_lists_one_level_without_recursing() {
+        let root = unique_temp_dir("lazy");
+        fs::create_dir_all(root.join("sub")).unwrap();
+        fs::write(root.join("sub").join("deep.txt"), b"x").unwrap();
+        fs::write(root.join("top.txt"), b"y").unwrap();
+
+        let entries: Vec<FileNode> = read_children_of_with_options(&root, &WalkOptions::default())
+            .unwrap()
+            .filter_map(|r| r.ok())
+            .collect();
+
+        let sub = entries.iter().find(|c| c.name == "sub").unwrap();
+        assert!(sub.children.is_none());
+        assert_eq!(sub.has_more, Some(true));
+
+        let _ = fs::remove_dir_all(&root);
+    }
 }
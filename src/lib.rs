@@ -1,4 +1,21 @@
+pub mod cache;
+pub mod duplicates;
 pub mod folder_formatter;
 pub mod folder_selector;
+pub mod parallel_walk;
+pub mod symlink;
+pub mod walk_options;
 
-pub use crate::folder_selector::{FileNode, read_directory, read_directory_fast, collect_paths};
\ No newline at end of file
+pub use crate::cache::{read_directory_cached, read_index, write_index};
+pub use crate::duplicates::{find_duplicates, DuplicateMethod};
+pub use crate::folder_selector::{
+    collect_paths, collect_paths_with_options, read_children_of, read_children_of_with_options,
+    read_directory, read_directory_fast, read_directory_fast_with_options,
+    read_directory_with_options, FileNode,
+};
+pub use crate::parallel_walk::{
+    collect_paths_parallel, collect_paths_parallel_with_options,
+    collect_paths_parallel_with_progress, read_directory_fast_parallel, ProgressData,
+};
+pub use crate::symlink::LinkStatus;
+pub use crate::walk_options::{PatternMatcher, WalkOptions};
\ No newline at end of file
@@ -0,0 +1,409 @@
+use std::collections::{HashSet, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::Sender;
+
+use crate::folder_formatter::file_tree::FileType as FT;
+use crate::folder_selector::{map_file_type, rel_path_str};
+use crate::symlink::dir_identity;
+use crate::walk_options::{read_gitignore_patterns, PatternMatcher, WalkOptions};
+
+/// A snapshot pushed on the progress channel a few times per second so a UI
+/// can show a live counter during a long parallel scan.
+#[derive(Clone, Debug, Default)]
+pub struct ProgressData {
+    pub dirs_scanned: u64,
+    pub entries_found: u64,
+    pub current_path: String,
+}
+
+/// One directory still to scan, along with the filter state it was reached
+/// with — mirrors the per-frame `(matcher, ancestors)` the serial stack walk
+/// in `collect_paths_with_options` carries, so a `.gitignore` layered in by
+/// a parent or a symlink cycle is caught the same way under either walk.
+struct WorkItem {
+    dir: PathBuf,
+    depth: u32,
+    matcher: PatternMatcher,
+    ancestors: HashSet<(u64, u64)>,
+}
+
+/// Shared work-stealing queue: a deque of directories still to scan plus a
+/// count of workers currently popping from it. A worker only declares the
+/// walk finished once the queue is empty *and* no one else is mid-pop,
+/// otherwise it would quit while a sibling is about to push more work.
+struct WorkQueue {
+    dirs: Mutex<VecDeque<WorkItem>>,
+    active: Mutex<usize>,
+    cond: Condvar,
+}
+
+impl WorkQueue {
+    fn new(root: PathBuf, matcher: PatternMatcher, ancestors: HashSet<(u64, u64)>) -> Self {
+        let mut dirs = VecDeque::new();
+        dirs.push_back(WorkItem {
+            dir: root,
+            depth: 0,
+            matcher,
+            ancestors,
+        });
+        WorkQueue {
+            dirs: Mutex::new(dirs),
+            active: Mutex::new(0),
+            cond: Condvar::new(),
+        }
+    }
+
+    /// Block until either a directory is available or every worker is idle
+    /// with nothing left to do (in which case `None` signals "done").
+    fn pop(&self) -> Option<WorkItem> {
+        let mut dirs = self.dirs.lock().unwrap();
+        loop {
+            if let Some(next) = dirs.pop_front() {
+                *self.active.lock().unwrap() += 1;
+                return Some(next);
+            }
+            let active = *self.active.lock().unwrap();
+            if active == 0 {
+                self.cond.notify_all();
+                return None;
+            }
+            dirs = self.cond.wait(dirs).unwrap();
+        }
+    }
+
+    fn push(&self, item: WorkItem) {
+        self.dirs.lock().unwrap().push_back(item);
+        self.cond.notify_all();
+    }
+
+    fn done_with_one(&self) {
+        *self.active.lock().unwrap() -= 1;
+        self.cond.notify_all();
+    }
+}
+
+/// A few times per second is plenty for a live counter; anything tighter
+/// just floods the channel on a large/fast tree.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Shared across workers so the interval is enforced globally, not per
+/// worker (otherwise N workers each sending every 200ms still floods the
+/// channel at N times the intended rate).
+struct ProgressThrottle {
+    last_sent: Mutex<Instant>,
+}
+
+impl ProgressThrottle {
+    fn new() -> Self {
+        ProgressThrottle {
+            // starts already due, so the first directory scanned reports immediately
+            last_sent: Mutex::new(Instant::now() - PROGRESS_INTERVAL),
+        }
+    }
+
+    fn try_tick(&self) -> bool {
+        let mut last_sent = self.last_sent.lock().unwrap();
+        if last_sent.elapsed() >= PROGRESS_INTERVAL {
+            *last_sent = Instant::now();
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Fan a directory tree scan out across `threads` workers. Each worker pops
+/// a directory off the shared deque, reads its entries via
+/// `DirEntry.file_type()`, pushes child directories back for other workers
+/// to pick up, and appends results into a shared mutex-guarded output.
+///
+/// Workers append in whatever order they finish, so a final sort pass runs
+/// once all workers are done: every directory entry comes before every other
+/// entry, and each group is ordered by lowercased full path. The serial
+/// `collect_paths` does not sort, so the two orders generally differ.
+pub fn collect_paths_parallel(
+    root: &Path,
+    max_depth: Option<u32>,
+    threads: usize,
+) -> Vec<(String, FT, u64, Option<u64>)> {
+    collect_paths_parallel_with_options(root, max_depth, threads, &WalkOptions::default())
+}
+
+/// Like `collect_paths_parallel`, but applies an opt-in `WalkOptions` filter
+/// layer (explicit ignore patterns, `.gitignore` layering, hidden-file
+/// visibility, symlink following) while walking, the same as
+/// `collect_paths_with_options` does for the serial walk.
+pub fn collect_paths_parallel_with_options(
+    root: &Path,
+    max_depth: Option<u32>,
+    threads: usize,
+    opts: &WalkOptions,
+) -> Vec<(String, FT, u64, Option<u64>)> {
+    collect_paths_parallel_with_progress(root, max_depth, threads, opts, None)
+}
+
+/// Like `collect_paths_parallel_with_options`, but also reports progress on
+/// `progress` roughly a few times per second so a UI can show a live counter.
+pub fn collect_paths_parallel_with_progress(
+    root: &Path,
+    max_depth: Option<u32>,
+    threads: usize,
+    opts: &WalkOptions,
+    progress: Option<Sender<ProgressData>>,
+) -> Vec<(String, FT, u64, Option<u64>)> {
+    let threads = threads.max(1);
+    let root_buf = root.to_path_buf();
+    // seed with the root's own identity so a symlink cycle that loops back
+    // to the walk root is caught the same way the serial walk catches it
+    let root_ancestors: HashSet<(u64, u64)> = dir_identity(root).into_iter().collect();
+    let queue = Arc::new(WorkQueue::new(root_buf.clone(), opts.compile(), root_ancestors));
+    let results: Arc<Mutex<Vec<(String, FT, u64, Option<u64>)>>> = Arc::new(Mutex::new(Vec::new()));
+    let dirs_scanned = Arc::new(Mutex::new(0u64));
+    let entries_found = Arc::new(Mutex::new(0u64));
+    let throttle = Arc::new(ProgressThrottle::new());
+
+    let mut handles = Vec::with_capacity(threads);
+    for _ in 0..threads {
+        let queue = Arc::clone(&queue);
+        let results = Arc::clone(&results);
+        let dirs_scanned = Arc::clone(&dirs_scanned);
+        let entries_found = Arc::clone(&entries_found);
+        let throttle = Arc::clone(&throttle);
+        let progress = progress.clone();
+        let opts = opts.clone();
+        let root = root_buf.clone();
+
+        handles.push(thread::spawn(move || {
+            while let Some(WorkItem { dir, depth, matcher, ancestors }) = queue.pop() {
+                let mut matcher = matcher;
+                if opts.respect_gitignore {
+                    if let Some(patterns) = read_gitignore_patterns(&dir) {
+                        matcher.push_patterns(&patterns);
+                    }
+                }
+
+                let mut local = Vec::new();
+                if let Ok(rd) = fs::read_dir(&dir) {
+                    for entry_res in rd {
+                        if let Ok(entry) = entry_res {
+                            let path = entry.path();
+
+                            if !opts.include_hidden {
+                                if let Some(fname) = path.file_name().and_then(|n| n.to_str()) {
+                                    if fname.starts_with('.') {
+                                        continue;
+                                    }
+                                }
+                            }
+
+                            let ft = entry
+                                .file_type()
+                                .or_else(|_| fs::symlink_metadata(&path).map(|m| m.file_type()))
+                                .ok();
+
+                            if !matcher.is_empty() {
+                                let rel = rel_path_str(&root, &path);
+                                let is_dir = ft.map(|ft| ft.is_dir()).unwrap_or(false);
+                                if matcher.matches(&rel, is_dir) {
+                                    continue;
+                                }
+                            }
+
+                            if let Some(ft) = ft {
+                                let mut mapped = map_file_type(ft);
+                                let mut followed_identity: Option<(u64, u64)> = None;
+
+                                // a followed symlink-to-directory is walked like a
+                                // real directory, guarded by the same
+                                // ancestor-chain cycle check the serial walk uses
+                                if ft.is_symlink() && opts.follow_symlinks {
+                                    if let Ok(target_meta) = fs::metadata(&path) {
+                                        if target_meta.is_dir() {
+                                            match dir_identity(&path) {
+                                                Some(id) if ancestors.contains(&id) => {
+                                                    // cycle: leave classified as a link
+                                                }
+                                                id => {
+                                                    mapped = FT::Directory;
+                                                    followed_identity = id;
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+
+                                let meta = entry.metadata().or_else(|_| fs::symlink_metadata(&path)).ok();
+                                let size = meta.as_ref().map(|m| m.len()).unwrap_or(0);
+                                let modified = meta.as_ref().and_then(|m| {
+                                    m.modified()
+                                        .ok()?
+                                        .duration_since(std::time::UNIX_EPOCH)
+                                        .ok()
+                                        .map(|d| d.as_secs())
+                                });
+
+                                local.push((path.to_string_lossy().into_owned(), mapped.clone(), size, modified));
+
+                                if matches!(mapped, FT::Directory)
+                                    && max_depth.map_or(true, |m| depth + 1 <= m)
+                                {
+                                    let mut child_ancestors = ancestors.clone();
+                                    if let Some(id) = followed_identity.or_else(|| dir_identity(&path)) {
+                                        child_ancestors.insert(id);
+                                    }
+                                    queue.push(WorkItem {
+                                        dir: path,
+                                        depth: depth + 1,
+                                        matcher: matcher.clone(),
+                                        ancestors: child_ancestors,
+                                    });
+                                }
+                            }
+                        }
+                    }
+                }
+
+                let scanned = {
+                    let mut n = dirs_scanned.lock().unwrap();
+                    *n += 1;
+                    *n
+                };
+                let found = {
+                    let mut n = entries_found.lock().unwrap();
+                    *n += local.len() as u64;
+                    *n
+                };
+                if let Some(tx) = &progress {
+                    if throttle.try_tick() {
+                        let _ = tx.try_send(ProgressData {
+                            dirs_scanned: scanned,
+                            entries_found: found,
+                            current_path: dir.to_string_lossy().into_owned(),
+                        });
+                    }
+                }
+
+                results.lock().unwrap().extend(local);
+                queue.done_with_one();
+            }
+        }));
+    }
+
+    for h in handles {
+        let _ = h.join();
+    }
+
+    let mut out = Arc::try_unwrap(results)
+        .unwrap_or_else(|arc| Mutex::new(arc.lock().unwrap().clone()))
+        .into_inner()
+        .unwrap();
+
+    // restore the deterministic folder-first/name ordering as a final pass,
+    // since workers append in whatever order they finish
+    out.sort_by(|a, b| match (matches!(a.1, FT::Directory), matches!(b.1, FT::Directory)) {
+        (true, false) => std::cmp::Ordering::Less,
+        (false, true) => std::cmp::Ordering::Greater,
+        _ => a.0.to_lowercase().cmp(&b.0.to_lowercase()),
+    });
+
+    out
+}
+
+/// Parallel variant of `read_directory_fast`: scans with `threads` workers
+/// before formatting, useful once a tree is large enough to be I/O-bound.
+pub fn read_directory_fast_parallel<P: AsRef<Path>>(
+    path: P,
+    max_depth: Option<u32>,
+    threads: usize,
+) -> Result<String, std::io::Error> {
+    let root = path.as_ref();
+    if !root.exists() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("Path not found: {}", root.to_string_lossy()),
+        ));
+    }
+
+    let children = collect_paths_parallel(root, max_depth, threads);
+    // format_paths only needs (path, type) pairs; size/modified are for callers
+    // like find_duplicates that want them without a second walk
+    let for_format: Vec<(String, FT)> = children
+        .into_iter()
+        .map(|(path, ft, _size, _modified)| (path, ft))
+        .collect();
+    Ok(crate::folder_formatter::json_formatting::format_paths(
+        &root.to_string_lossy(),
+        for_format,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::folder_selector::collect_paths_with_options;
+
+    fn unique_temp_dir(label: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "fwalker-rs-parallel-test-{}-{}-{}",
+            label,
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn parallel_walk_finds_the_same_paths_as_the_serial_walk() {
+        let root = unique_temp_dir("parity");
+        fs::create_dir_all(root.join("a").join("b")).unwrap();
+        fs::write(root.join("a").join("b").join("leaf.txt"), b"hi").unwrap();
+        fs::write(root.join("top.txt"), b"hello").unwrap();
+
+        let opts = WalkOptions::default();
+        let mut serial: Vec<String> = collect_paths_with_options(&root, None, &opts)
+            .into_iter()
+            .map(|(p, ..)| p)
+            .collect();
+        let mut parallel: Vec<String> = collect_paths_parallel_with_options(&root, None, 4, &opts)
+            .into_iter()
+            .map(|(p, ..)| p)
+            .collect();
+        serial.sort();
+        parallel.sort();
+
+        assert_eq!(serial, parallel);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn parallel_walk_respects_ignore_patterns() {
+        let root = unique_temp_dir("ignore");
+        fs::create_dir_all(root.join("node_modules")).unwrap();
+        fs::write(root.join("node_modules").join("pkg.json"), b"{}").unwrap();
+        fs::write(root.join("keep.txt"), b"hi").unwrap();
+
+        let opts = WalkOptions {
+            patterns: vec!["node_modules/".to_string()],
+            ..WalkOptions::default()
+        };
+        let paths: Vec<String> = collect_paths_parallel_with_options(&root, None, 2, &opts)
+            .into_iter()
+            .map(|(p, ..)| p)
+            .collect();
+
+        assert!(paths.iter().any(|p| p.ends_with("keep.txt")));
+        assert!(!paths.iter().any(|p| p.contains("node_modules")));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,90 @@
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+/// Outcome of resolving a symlink encountered while walking.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LinkStatus {
+    /// The link target exists.
+    Healthy,
+    /// The link target does not exist on disk.
+    Broken,
+    /// Following the link would re-enter a directory already on the
+    /// current ancestor chain; the walk stops here instead of looping.
+    InfiniteRecursion,
+}
+
+/// A `(device, inode)` pair (or platform equivalent) uniquely identifying a
+/// directory on disk, used to detect symlink cycles along the current
+/// ancestor chain.
+#[cfg(unix)]
+pub fn dir_identity(p: &Path) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    let meta = fs::metadata(p).ok()?;
+    Some((meta.dev(), meta.ino()))
+}
+
+/// Windows has no inode, but `(volume_serial_number, file_index)` plays the
+/// same role of uniquely identifying a file/directory on disk.
+#[cfg(windows)]
+pub fn dir_identity(p: &Path) -> Option<(u64, u64)> {
+    use std::os::windows::fs::MetadataExt;
+    let meta = fs::metadata(p).ok()?;
+    Some((meta.volume_serial_number()? as u64, meta.file_index()?))
+}
+
+#[cfg(not(any(unix, windows)))]
+pub fn dir_identity(_p: &Path) -> Option<(u64, u64)> {
+    None
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn unique_temp_dir(label: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "fwalker-rs-symlink-test-{}-{}-{}",
+            label,
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn same_directory_has_same_identity_every_time() {
+        let dir = unique_temp_dir("same");
+        assert_eq!(dir_identity(&dir), dir_identity(&dir));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn distinct_directories_have_distinct_identities() {
+        let a = unique_temp_dir("a");
+        let b = unique_temp_dir("b");
+        assert_ne!(dir_identity(&a), dir_identity(&b));
+        let _ = fs::remove_dir_all(&a);
+        let _ = fs::remove_dir_all(&b);
+    }
+
+    #[test]
+    fn symlink_to_a_directory_resolves_to_the_target_identity() {
+        let dir = unique_temp_dir("target");
+        let link = dir.with_file_name(format!(
+            "{}-link",
+            dir.file_name().unwrap().to_string_lossy()
+        ));
+        std::os::unix::fs::symlink(&dir, &link).unwrap();
+
+        assert_eq!(dir_identity(&dir), dir_identity(&link));
+
+        let _ = fs::remove_file(&link);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,223 @@
+use std::fs;
+use std::path::Path;
+
+/// Opt-in filter configuration for a directory walk: explicit glob-style
+/// ignore patterns, `.gitignore` layering, and hidden-file visibility.
+///
+/// Nothing is filtered unless a caller opts in, so existing callers of
+/// `read_directory`/`collect_paths` keep their current behaviour.
+#[derive(Clone, Debug)]
+pub struct WalkOptions {
+    pub patterns: Vec<String>,
+    pub respect_gitignore: bool,
+    pub include_hidden: bool,
+    /// When set, a symlink to a directory is recursed into like a real
+    /// directory instead of being left as a leaf `FT::Link`/`FileNode`.
+    pub follow_symlinks: bool,
+}
+
+impl Default for WalkOptions {
+    fn default() -> Self {
+        WalkOptions {
+            patterns: Vec::new(),
+            respect_gitignore: false,
+            // hidden entries stay visible unless a caller opts out
+            include_hidden: true,
+            follow_symlinks: false,
+        }
+    }
+}
+
+impl WalkOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Compile `patterns` into a matcher ready to be threaded through the walk.
+    pub fn compile(&self) -> PatternMatcher {
+        PatternMatcher::new(&self.patterns)
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Rule {
+    glob: String,
+    negate: bool,
+    /// Set for a pattern written with a trailing `/` (the standard
+    /// `.gitignore` directory form, e.g. `node_modules/`) — it only ever
+    /// matches a directory, never a file of the same name.
+    dir_only: bool,
+}
+
+fn parse_rule(pattern: &str) -> Rule {
+    let negate = pattern.starts_with('!');
+    let pattern = pattern.strip_prefix('!').unwrap_or(pattern);
+    let dir_only = pattern.ends_with('/');
+    let glob = pattern.strip_suffix('/').unwrap_or(pattern).to_string();
+    Rule {
+        glob,
+        negate,
+        dir_only,
+    }
+}
+
+/// Compiled ignore rules, evaluated in order so a later `!` negation can
+/// re-include a path an earlier pattern excluded (gitignore semantics).
+#[derive(Clone, Debug, Default)]
+pub struct PatternMatcher {
+    rules: Vec<Rule>,
+}
+
+impl PatternMatcher {
+    pub fn new(patterns: &[String]) -> Self {
+        PatternMatcher {
+            rules: patterns.iter().map(|p| parse_rule(p)).collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Layer more rules on top, e.g. a child directory's own `.gitignore`.
+    /// They are evaluated after the existing rules, so a child pattern can
+    /// override a parent one just like nested `.gitignore` files do.
+    pub fn push_patterns(&mut self, patterns: &[String]) {
+        self.rules.extend(patterns.iter().map(|p| parse_rule(p)));
+    }
+
+    /// Test a `/`-separated path relative to the walk root. `is_dir` gates
+    /// directory-only patterns (a trailing-slash rule like `target/` never
+    /// matches a plain file). The last rule that matches wins, so later
+    /// (more specific) patterns take priority.
+    pub fn matches(&self, rel_path: &str, is_dir: bool) -> bool {
+        let mut matched = false;
+        for rule in &self.rules {
+            if rule.dir_only && !is_dir {
+                continue;
+            }
+            if glob_match(&rule.glob, rel_path) {
+                matched = !rule.negate;
+            }
+        }
+        matched
+    }
+}
+
+/// Read and parse a directory's `.gitignore`, if any, skipping blank lines
+/// and `#` comments. Returns `None` when there is no `.gitignore` to layer in.
+pub fn read_gitignore_patterns(dir: &Path) -> Option<Vec<String>> {
+    let content = fs::read_to_string(dir.join(".gitignore")).ok()?;
+    let patterns: Vec<String> = content
+        .lines()
+        .map(|l| l.trim())
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(|l| l.to_string())
+        .collect();
+    if patterns.is_empty() {
+        None
+    } else {
+        Some(patterns)
+    }
+}
+
+/// Minimal glob matcher supporting `*` (any run within one path segment),
+/// `**` (any number of segments) and literal segments, matched
+/// segment-by-segment the way `.gitignore` patterns are.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    if !pattern.contains('/') {
+        // a pattern with no slash is anchored at any depth, like
+        // .gitignore's implicit `**/` prefix, not just at the walk root
+        let text_segs: Vec<&str> = text.split('/').collect();
+        return text_segs.iter().any(|seg| match_segment(pattern, seg));
+    }
+
+    let pat_segs: Vec<&str> = pattern.split('/').collect();
+    let text_segs: Vec<&str> = text.split('/').collect();
+    match_segments(&pat_segs, &text_segs)
+}
+
+fn match_segments(pat: &[&str], text: &[&str]) -> bool {
+    if pat.is_empty() {
+        return text.is_empty();
+    }
+    if pat[0] == "**" {
+        if pat.len() == 1 {
+            return true;
+        }
+        return (0..=text.len()).any(|i| match_segments(&pat[1..], &text[i..]));
+    }
+    if text.is_empty() {
+        return false;
+    }
+    match_segment(pat[0], text[0]) && match_segments(&pat[1..], &text[1..])
+}
+
+fn match_segment(pat: &str, text: &str) -> bool {
+    let pat_chars: Vec<char> = pat.chars().collect();
+    let text_chars: Vec<char> = text.chars().collect();
+    match_glob(&pat_chars, &text_chars)
+}
+
+fn match_glob(pat: &[char], text: &[char]) -> bool {
+    match (pat.first(), text.first()) {
+        (None, None) => true,
+        (Some('*'), _) => {
+            match_glob(&pat[1..], text) || (!text.is_empty() && match_glob(pat, &text[1..]))
+        }
+        (Some(pc), Some(tc)) if pc == tc => match_glob(&pat[1..], &text[1..]),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slashless_pattern_matches_at_any_depth() {
+        let m = PatternMatcher::new(&["node_modules".to_string()]);
+        assert!(m.matches("node_modules", true));
+        assert!(m.matches("packages/app/node_modules", true));
+        assert!(!m.matches("packages/app/node_modules_backup", true));
+    }
+
+    #[test]
+    fn slashless_glob_matches_at_any_depth() {
+        let m = PatternMatcher::new(&["*.log".to_string()]);
+        assert!(m.matches("debug.log", false));
+        assert!(m.matches("sub/debug.log", false));
+        assert!(!m.matches("sub/debug.txt", false));
+    }
+
+    #[test]
+    fn trailing_slash_pattern_matches_directories_only() {
+        let m = PatternMatcher::new(&["node_modules/".to_string()]);
+        assert!(m.matches("node_modules", true));
+        assert!(m.matches("a/b/node_modules", true));
+        // a plain file named "node_modules" is not a directory and should
+        // not be excluded by the directory-only form of the rule
+        assert!(!m.matches("node_modules", false));
+    }
+
+    #[test]
+    fn trailing_slash_build_pattern_matches_nested_dir() {
+        let m = PatternMatcher::new(&["build/".to_string()]);
+        assert!(m.matches("build", true));
+        assert!(m.matches("a/build", true));
+    }
+
+    #[test]
+    fn negation_reincludes_after_earlier_exclude() {
+        let m = PatternMatcher::new(&["*.log".to_string(), "!keep.log".to_string()]);
+        assert!(m.matches("debug.log", false));
+        assert!(!m.matches("keep.log", false));
+    }
+
+    #[test]
+    fn child_patterns_override_parent_via_push_patterns() {
+        let mut m = PatternMatcher::new(&["*.log".to_string()]);
+        m.push_patterns(&["!keep.log".to_string()]);
+        assert!(!m.matches("keep.log", false));
+    }
+}